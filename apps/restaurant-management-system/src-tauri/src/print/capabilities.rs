@@ -0,0 +1,332 @@
+//! Query what a printer actually supports (media sizes, resolutions,
+//! duplex, color) so the UI can offer only valid options instead of
+//! hardcoding 80mm/58mm thermal paper.
+
+use super::exec::{self, DEFAULT_QUERY_TIMEOUT_MS};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+/// A page/media size a printer supports.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MediaSize {
+    /// The driver's name for this media, e.g. `Custom.80x200mm` or `A4`.
+    pub name: String,
+    /// Width in mm, when known.
+    pub width_mm: Option<f64>,
+    /// Height in mm, when known (continuous thermal rolls have none).
+    pub height_mm: Option<f64>,
+    pub is_default: bool,
+}
+
+/// Capabilities reported by a printer's driver.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrinterCapabilities {
+    pub media_sizes: Vec<MediaSize>,
+    /// Supported resolutions, e.g. `["203dpi", "300dpi"]`.
+    pub resolutions: Vec<String>,
+    pub supports_color: bool,
+    pub supports_duplex: bool,
+    pub default_media: Option<String>,
+}
+
+impl PrinterCapabilities {
+    /// Find the supported media whose width is closest to `width_mm`,
+    /// for validating/correcting [`super::PrintOptions::paper_width`].
+    pub fn closest_media_by_width(&self, width_mm: u32) -> Option<&MediaSize> {
+        self.media_sizes
+            .iter()
+            .filter(|m| m.width_mm.is_some_and(|w| w.is_finite()))
+            .min_by(|a, b| {
+                let da = (a.width_mm.unwrap() - width_mm as f64).abs();
+                let db = (b.width_mm.unwrap() - width_mm as f64).abs();
+                // `MediaSize` is public and deserializable, so a width can
+                // be non-finite even though `parse_media_dimensions`
+                // rejects such values; those are filtered out above and
+                // must never turn into a panic here.
+                da.partial_cmp(&db).unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+}
+
+/// Parse the width (and height, if present) encoded in a CUPS media
+/// name, e.g. `Custom.80x200mm` -> `(80.0, Some(200.0))`, or fall back
+/// to well-known standard sizes.
+fn parse_media_dimensions(name: &str) -> (Option<f64>, Option<f64>) {
+    if let Some(dims) = name.strip_prefix("Custom.").and_then(|s| s.strip_suffix("mm")) {
+        if let Some((w, h)) = dims.split_once('x') {
+            if let (Ok(w), Ok(h)) = (w.parse::<f64>(), h.parse::<f64>()) {
+                if w.is_finite() && h.is_finite() {
+                    return (Some(w), Some(h));
+                }
+            }
+        }
+    }
+
+    match name {
+        "A4" => (Some(210.0), Some(297.0)),
+        "A5" => (Some(148.0), Some(210.0)),
+        "Letter" => (Some(215.9), Some(279.4)),
+        "Legal" => (Some(215.9), Some(355.6)),
+        _ => (None, None),
+    }
+}
+
+/// Parse one `lpoptions -l` line of the form
+/// `OptionName/Label: value1 *defaultValue value2` into (option name,
+/// values). The default keeps its leading `*` in `values`.
+fn parse_lpoptions_line(line: &str) -> Option<(&str, Vec<&str>)> {
+    let (key, values) = line.split_once(':')?;
+    let option_name = key.split('/').next()?.trim();
+    let values = values.split_whitespace().collect();
+    Some((option_name, values))
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+pub async fn get_printer_capabilities_unix(printer: &str) -> Result<PrinterCapabilities, String> {
+    let mut cmd = Command::new("lpoptions");
+    cmd.args(["-p", printer, "-l"]);
+    let output = exec::exec_checked(cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map_err(|e| format!("Failed to query printer capabilities: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output);
+
+    let mut media_sizes = Vec::new();
+    let mut resolutions = Vec::new();
+    let mut supports_color = false;
+    let mut supports_duplex = false;
+    let mut default_media = None;
+
+    for line in stdout.lines() {
+        let Some((option, values)) = parse_lpoptions_line(line) else {
+            continue;
+        };
+
+        match option {
+            "PageSize" => {
+                for value in values {
+                    let is_default = value.starts_with('*');
+                    let name = value.trim_start_matches('*').to_string();
+                    let (width_mm, height_mm) = parse_media_dimensions(&name);
+                    if is_default {
+                        default_media = Some(name.clone());
+                    }
+                    media_sizes.push(MediaSize {
+                        name,
+                        width_mm,
+                        height_mm,
+                        is_default,
+                    });
+                }
+            }
+            "Resolution" => {
+                resolutions = values
+                    .into_iter()
+                    .map(|v| v.trim_start_matches('*').to_string())
+                    .collect();
+            }
+            "ColorModel" => {
+                supports_color = values
+                    .iter()
+                    .any(|v| v.trim_start_matches('*').eq_ignore_ascii_case("RGB"));
+            }
+            "Duplex" => {
+                supports_duplex = values.iter().any(|v| {
+                    let v = v.trim_start_matches('*');
+                    v == "DuplexNoTumble" || v == "DuplexTumble"
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(PrinterCapabilities {
+        media_sizes,
+        resolutions,
+        supports_color,
+        supports_duplex,
+        default_media,
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub async fn get_printer_capabilities_windows(
+    printer: &str,
+) -> Result<PrinterCapabilities, String> {
+    let printer = exec::escape_powershell_arg(printer);
+    let script = format!(
+        r#"
+        $config = Get-PrintConfiguration -PrinterName "{printer}"
+        $resolutions = Get-PrinterProperty -PrinterName "{printer}" |
+            Where-Object {{ $_.PropertyName -match 'Resolution' }} |
+            Select-Object -ExpandProperty Value
+        [PSCustomObject]@{{
+            PaperSize = $config.PaperSize
+            Color = $config.Color
+            Duplex = $config.DuplexingMode
+            Resolutions = @($resolutions)
+        }} | ConvertTo-Json -Depth 4
+        "#,
+        printer = printer
+    );
+
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-Command", &script]);
+    let output = exec::exec_checked(cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map_err(|e| format!("Failed to query printer capabilities: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output);
+
+    #[derive(Deserialize)]
+    struct WinCapabilities {
+        #[serde(rename = "PaperSize")]
+        paper_size: Option<String>,
+        #[serde(rename = "Color")]
+        color: Option<String>,
+        #[serde(rename = "Duplex")]
+        duplex: Option<String>,
+        /// Raw `Get-PrinterProperty` values for resolution-related
+        /// properties. The driver reports these as either strings
+        /// (e.g. `"300dpi"`) or bare numeric DPI values, so this is
+        /// kept untyped and normalized in [`get_printer_capabilities_windows`].
+        #[serde(rename = "Resolutions", default)]
+        resolutions: Vec<serde_json::Value>,
+    }
+
+    let parsed: WinCapabilities = serde_json::from_str(&stdout)
+        .map_err(|e| format!("Failed to parse printer capabilities: {} - Output: {}", e, stdout))?;
+
+    let default_media = parsed.paper_size.clone();
+    let media_sizes = parsed
+        .paper_size
+        .map(|name| {
+            let (width_mm, height_mm) = parse_media_dimensions(&name);
+            vec![MediaSize {
+                name,
+                width_mm,
+                height_mm,
+                is_default: true,
+            }]
+        })
+        .unwrap_or_default();
+
+    let resolutions = parsed
+        .resolutions
+        .iter()
+        .filter_map(|value| match value {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    Ok(PrinterCapabilities {
+        media_sizes,
+        resolutions,
+        supports_color: parsed
+            .color
+            .map(|c| !c.eq_ignore_ascii_case("Monochrome"))
+            .unwrap_or(false),
+        supports_duplex: parsed
+            .duplex
+            .map(|d| !d.eq_ignore_ascii_case("OneSided"))
+            .unwrap_or(false),
+        default_media,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_media_dimensions_reads_custom_mm_size() {
+        assert_eq!(
+            parse_media_dimensions("Custom.80x200mm"),
+            (Some(80.0), Some(200.0))
+        );
+    }
+
+    #[test]
+    fn parse_media_dimensions_falls_back_to_known_standard_sizes() {
+        assert_eq!(parse_media_dimensions("A4"), (Some(210.0), Some(297.0)));
+    }
+
+    #[test]
+    fn parse_media_dimensions_returns_none_for_unknown_names() {
+        assert_eq!(parse_media_dimensions("Weird.Size"), (None, None));
+    }
+
+    #[test]
+    fn parse_media_dimensions_does_not_panic_on_non_numeric_custom_size() {
+        // A misbehaving driver could report something like this; it
+        // should be treated as unparseable rather than crash.
+        assert_eq!(parse_media_dimensions("Custom.NaNx200mm"), (None, None));
+    }
+
+    #[test]
+    fn parse_lpoptions_line_splits_name_and_values() {
+        let (name, values) =
+            parse_lpoptions_line("PageSize/Media Size: Letter *Custom.80x200mm A4").unwrap();
+        assert_eq!(name, "PageSize");
+        assert_eq!(values, vec!["Letter", "*Custom.80x200mm", "A4"]);
+    }
+
+    #[test]
+    fn parse_lpoptions_line_returns_none_without_a_colon() {
+        assert!(parse_lpoptions_line("not an option line").is_none());
+    }
+
+    fn media(name: &str, width_mm: Option<f64>) -> MediaSize {
+        MediaSize {
+            name: name.to_string(),
+            width_mm,
+            height_mm: None,
+            is_default: false,
+        }
+    }
+
+    #[test]
+    fn closest_media_by_width_picks_the_nearest_match() {
+        let caps = PrinterCapabilities {
+            media_sizes: vec![media("58mm", Some(58.0)), media("80mm", Some(80.0))],
+            resolutions: vec![],
+            supports_color: false,
+            supports_duplex: false,
+            default_media: None,
+        };
+
+        assert_eq!(caps.closest_media_by_width(76).unwrap().name, "80mm");
+        assert_eq!(caps.closest_media_by_width(60).unwrap().name, "58mm");
+    }
+
+    #[test]
+    fn closest_media_by_width_ignores_non_finite_widths_instead_of_panicking() {
+        let caps = PrinterCapabilities {
+            media_sizes: vec![
+                media("broken", Some(f64::NAN)),
+                media("80mm", Some(80.0)),
+            ],
+            resolutions: vec![],
+            supports_color: false,
+            supports_duplex: false,
+            default_media: None,
+        };
+
+        assert_eq!(caps.closest_media_by_width(80).unwrap().name, "80mm");
+    }
+
+    #[test]
+    fn closest_media_by_width_returns_none_when_no_size_has_a_width() {
+        let caps = PrinterCapabilities {
+            media_sizes: vec![media("continuous", None)],
+            resolutions: vec![],
+            supports_color: false,
+            supports_duplex: false,
+            default_media: None,
+        };
+
+        assert!(caps.closest_media_by_width(80).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+//! Timeout-guarded external command execution.
+//!
+//! Every platform function in the print module shells out to `lp`,
+//! `lpstat`, `powershell`, etc. Without a timeout, a hung or offline
+//! printer (or a stuck IE COM automation) blocks the command
+//! indefinitely. [`exec`] runs the child with a deadline, killing it
+//! on expiry, and returns a [`PrintError`] that lets callers tell
+//! "the printer didn't respond" apart from "the command ran and the
+//! printer rejected the job."
+
+use std::fmt;
+use std::process::{Output, Stdio};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+
+/// Default timeout for a quick status/discovery command (listing
+/// printers, polling job status, ...).
+pub const DEFAULT_QUERY_TIMEOUT_MS: u64 = 5_000;
+/// Default timeout for a command that sends a job to the printer.
+pub const DEFAULT_PRINT_TIMEOUT_MS: u64 = 15_000;
+
+/// Structured failure from running an external command.
+#[derive(Debug)]
+pub enum PrintError {
+    /// The command did not finish within its timeout and was killed.
+    Timeout,
+    /// The command could not even be spawned (not installed, etc).
+    SpawnFailed(String),
+    /// The command ran and exited with a non-zero status.
+    NonZeroExit { code: Option<i32>, stderr: String },
+    /// The command succeeded but its output couldn't be parsed.
+    ParseFailed(String),
+}
+
+impl fmt::Display for PrintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrintError::Timeout => {
+                write!(f, "Timeout: printer did not respond within the configured timeout")
+            }
+            PrintError::SpawnFailed(e) => write!(f, "Failed to run command: {}", e),
+            PrintError::NonZeroExit { code, stderr } => write!(
+                f,
+                "Command exited with status {}: {}",
+                code.map(|c| c.to_string())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                stderr
+            ),
+            PrintError::ParseFailed(e) => write!(f, "Failed to parse command output: {}", e),
+        }
+    }
+}
+
+impl From<PrintError> for String {
+    fn from(e: PrintError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Run `command`, killing it if it doesn't finish within `timeout_ms`.
+///
+/// stdout/stderr are drained concurrently with waiting for exit so a
+/// chatty child can't deadlock us by filling its pipe buffer before
+/// the timeout fires.
+pub async fn exec(mut command: tokio::process::Command, timeout_ms: u64) -> Result<Output, PrintError> {
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| PrintError::SpawnFailed(e.to_string()))?;
+
+    let mut stdout_pipe = child.stdout.take().expect("stdout was piped");
+    let mut stderr_pipe = child.stderr.take().expect("stderr was piped");
+
+    let stdout_task = tokio::spawn(async move {
+        let mut buf = Vec::new();
+        stdout_pipe.read_to_end(&mut buf).await.ok();
+        buf
+    });
+    let stderr_task = tokio::spawn(async move {
+        let mut buf = Vec::new();
+        stderr_pipe.read_to_end(&mut buf).await.ok();
+        buf
+    });
+
+    match tokio::time::timeout(Duration::from_millis(timeout_ms), child.wait()).await {
+        Ok(Ok(status)) => {
+            let stdout = stdout_task.await.unwrap_or_default();
+            let stderr = stderr_task.await.unwrap_or_default();
+            Ok(Output {
+                status,
+                stdout,
+                stderr,
+            })
+        }
+        Ok(Err(e)) => {
+            stdout_task.abort();
+            stderr_task.abort();
+            Err(PrintError::SpawnFailed(e.to_string()))
+        }
+        Err(_elapsed) => {
+            let _ = child.kill().await;
+            stdout_task.abort();
+            stderr_task.abort();
+            Err(PrintError::Timeout)
+        }
+    }
+}
+
+/// Escape a string for interpolation into a PowerShell double-quoted
+/// string literal, so a caller-supplied printer name or job id
+/// containing `"`, a backtick, or `$` can't break out of the literal
+/// and run arbitrary PowerShell (CWE-78).
+pub fn escape_powershell_arg(value: &str) -> String {
+    value
+        .replace('`', "``")
+        .replace('"', "`\"")
+        .replace('$', "`$")
+}
+
+/// Run `command` and require a zero exit status, returning its stdout.
+pub async fn exec_checked(
+    command: tokio::process::Command,
+    timeout_ms: u64,
+) -> Result<Vec<u8>, PrintError> {
+    let output = exec(command, timeout_ms).await?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(PrintError::NonZeroExit {
+            code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
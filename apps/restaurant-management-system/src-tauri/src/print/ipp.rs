@@ -0,0 +1,341 @@
+//! IPP (Internet Printing Protocol) discovery and direct printing.
+//!
+//! Lets kitchens print to a shared network printer without a locally
+//! configured CUPS queue: printers are discovered over DNS-SD/mDNS and
+//! printed to directly via an IPP `Print-Job` request, which works the
+//! same way on macOS/Windows/Linux since it doesn't shell out to any
+//! OS-specific print command.
+
+use std::time::Duration;
+
+/// mDNS service types that identify IPP printers.
+const IPP_SERVICE_TYPES: [&str; 2] = ["_ipp._tcp.local.", "_ipps._tcp.local."];
+
+/// How long to listen for mDNS responses before returning what we have.
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// An IPP printer discovered over DNS-SD/mDNS.
+pub struct IppPrinterDevice {
+    pub name: String,
+    pub uri: String,
+    pub description: Option<String>,
+}
+
+/// Browse `_ipp._tcp` / `_ipps._tcp` for network printers.
+pub fn discover_ipp_printers() -> Vec<IppPrinterDevice> {
+    let mdns = match mdns_sd::ServiceDaemon::new() {
+        Ok(daemon) => daemon,
+        Err(e) => {
+            log::warn!("Failed to start mDNS discovery: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut printers = Vec::new();
+
+    for service_type in IPP_SERVICE_TYPES {
+        let Ok(receiver) = mdns.browse(service_type) else {
+            continue;
+        };
+
+        let deadline = std::time::Instant::now() + DISCOVERY_TIMEOUT;
+        while let Some(remaining) = deadline.checked_duration_since(std::time::Instant::now()) {
+            let Ok(event) = receiver.recv_timeout(remaining) else {
+                break;
+            };
+
+            if let mdns_sd::ServiceEvent::ServiceResolved(info) = event {
+                let scheme = if service_type.starts_with("_ipps") {
+                    "ipps"
+                } else {
+                    "ipp"
+                };
+                let host = info.get_hostname().trim_end_matches('.');
+                let port = info.get_port();
+                let path = info
+                    .get_property_val_str("rp")
+                    .unwrap_or("ipp/print");
+
+                printers.push(IppPrinterDevice {
+                    name: info.get_fullname().to_string(),
+                    uri: format!("{}://{}:{}/{}", scheme, host, port, path),
+                    description: info.get_property_val_str("ty").map(|s| s.to_string()),
+                });
+            }
+        }
+    }
+
+    let _ = mdns.shutdown();
+    printers
+}
+
+/// Response to an IPP request: the status code plus any attributes we
+/// care about for job tracking.
+#[derive(Debug, Default)]
+pub struct IppResponse {
+    pub status_code: u16,
+    pub job_id: Option<String>,
+    pub job_state: Option<i32>,
+}
+
+impl IppResponse {
+    /// Whether `status_code` is one of the `successful-xxx` status
+    /// codes (RFC 8011 section 4.1.6): `0x0000..=0x00FF`. These are IPP
+    /// status codes, not HTTP ones — `successful-ok` is `0x0000`, so a
+    /// `200..300` HTTP-style range check would reject every successful
+    /// print.
+    pub fn is_success(&self) -> bool {
+        self.status_code <= 0x00FF
+    }
+}
+
+/// Encode an IPP `Print-Job` request (RFC 8011 operation layout: version,
+/// operation-id, request-id, operation-attributes-group, end-of-attributes,
+/// then the document body) and POST it to `uri`, killing the request if
+/// it hasn't finished within `timeout_ms` instead of hanging on an
+/// offline/unreachable network printer.
+pub async fn print_ipp(
+    uri: &str,
+    document: &[u8],
+    document_format: &str,
+    requesting_user: &str,
+    timeout_ms: u64,
+) -> Result<IppResponse, String> {
+    let request = build_print_job_request(uri, document_format, requesting_user, document);
+
+    // reqwest's connector only understands `http`/`https`; IPP URIs use
+    // `ipp`/`ipps`, which are the same protocol over HTTP/HTTPS, so
+    // rewrite before posting. This is the standard workaround used by
+    // real IPP client libraries. Both IPP schemes default to port 631
+    // rather than 80/443, so that port is written out when the URI gives
+    // none. The `printer-uri` attribute in the request body keeps the
+    // original `ipp(s)://` form, since that's what the spec requires there.
+    let post_url = match uri
+        .strip_prefix("ipps://")
+        .map(|rest| ("https", rest))
+        .or_else(|| uri.strip_prefix("ipp://").map(|rest| ("http", rest)))
+    {
+        Some((scheme, rest)) => {
+            let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            let host_port = authority.rsplit('@').next().unwrap_or(authority);
+            let has_port = host_port.rsplit(']').next().unwrap_or(host_port).contains(':');
+            if has_port {
+                format!("{}://{}{}", scheme, authority, path)
+            } else {
+                format!("{}://{}:631{}", scheme, authority, path)
+            }
+        }
+        None => uri.to_string(),
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_millis(timeout_ms))
+        .build()
+        .map_err(|e| format!("Failed to build IPP client: {}", e))?;
+
+    let response = client
+        .post(post_url)
+        .header("Content-Type", "application/ipp")
+        .body(request)
+        .send()
+        .await
+        .map_err(|e| format!("IPP request failed: {}", e))?;
+
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read IPP response: {}", e))?;
+
+    parse_response(&body)
+}
+
+/// IPP tag values used below (RFC 8010 section 3.5).
+mod tag {
+    pub const OPERATION_ATTRIBUTES: u8 = 0x01;
+    pub const JOB_ATTRIBUTES: u8 = 0x02;
+    pub const END_OF_ATTRIBUTES: u8 = 0x03;
+    pub const CHARSET: u8 = 0x47;
+    pub const NATURAL_LANGUAGE: u8 = 0x48;
+    pub const URI: u8 = 0x45;
+    pub const NAME_WITHOUT_LANGUAGE: u8 = 0x42;
+    pub const MIME_MEDIA_TYPE: u8 = 0x49;
+    pub const INTEGER: u8 = 0x21;
+    pub const ENUM: u8 = 0x23;
+}
+
+fn write_attribute(out: &mut Vec<u8>, value_tag: u8, name: &str, value: &[u8]) {
+    out.push(value_tag);
+    out.extend((name.len() as u16).to_be_bytes());
+    out.extend(name.as_bytes());
+    out.extend((value.len() as u16).to_be_bytes());
+    out.extend(value);
+}
+
+fn build_print_job_request(
+    uri: &str,
+    document_format: &str,
+    requesting_user: &str,
+    document: &[u8],
+) -> Vec<u8> {
+    let mut req = Vec::with_capacity(document.len() + 256);
+
+    req.extend([0x01, 0x01]); // version 1.1
+    req.extend(0x0002u16.to_be_bytes()); // operation-id: Print-Job
+    req.extend(1u32.to_be_bytes()); // request-id
+
+    req.push(tag::OPERATION_ATTRIBUTES);
+    write_attribute(&mut req, tag::CHARSET, "attributes-charset", b"utf-8");
+    write_attribute(
+        &mut req,
+        tag::NATURAL_LANGUAGE,
+        "attributes-natural-language",
+        b"en",
+    );
+    write_attribute(&mut req, tag::URI, "printer-uri", uri.as_bytes());
+    write_attribute(
+        &mut req,
+        tag::NAME_WITHOUT_LANGUAGE,
+        "requesting-user-name",
+        requesting_user.as_bytes(),
+    );
+    write_attribute(
+        &mut req,
+        tag::MIME_MEDIA_TYPE,
+        "document-format",
+        document_format.as_bytes(),
+    );
+
+    req.push(tag::END_OF_ATTRIBUTES);
+    req.extend(document);
+
+    req
+}
+
+/// Minimal IPP response parser: reads the status code and, if present,
+/// the `job-id`/`job-state` attributes from the job-attributes group.
+fn parse_response(body: &[u8]) -> Result<IppResponse, String> {
+    if body.len() < 8 {
+        return Err("IPP response too short".to_string());
+    }
+
+    let status_code = u16::from_be_bytes([body[2], body[3]]);
+    let mut response = IppResponse {
+        status_code,
+        ..Default::default()
+    };
+
+    let mut pos = 8;
+    let mut in_job_group = false;
+
+    while pos < body.len() {
+        let value_tag = body[pos];
+        pos += 1;
+
+        if value_tag == tag::END_OF_ATTRIBUTES {
+            break;
+        }
+        // Group delimiter tags are < 0x10.
+        if value_tag < 0x10 {
+            in_job_group = value_tag == tag::JOB_ATTRIBUTES;
+            continue;
+        }
+
+        if pos + 2 > body.len() {
+            break;
+        }
+        let name_len = u16::from_be_bytes([body[pos], body[pos + 1]]) as usize;
+        pos += 2 + name_len;
+
+        if pos + 2 > body.len() {
+            break;
+        }
+        let value_len = u16::from_be_bytes([body[pos], body[pos + 1]]) as usize;
+        pos += 2;
+        if pos + value_len > body.len() {
+            break;
+        }
+        let value = &body[pos..pos + value_len];
+        pos += value_len;
+
+        if !in_job_group {
+            continue;
+        }
+
+        let name_start = pos - value_len - 2 - name_len;
+        let name = &body[name_start..name_start + name_len];
+
+        match (name, value_tag) {
+            (b"job-id", tag::INTEGER) if value.len() == 4 => {
+                let id = i32::from_be_bytes([value[0], value[1], value[2], value[3]]);
+                response.job_id = Some(id.to_string());
+            }
+            (b"job-state", tag::ENUM) if value.len() == 4 => {
+                response.job_state =
+                    Some(i32::from_be_bytes([value[0], value[1], value[2], value[3]]));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a minimal IPP response header (version, status-code,
+    /// request-id, end-of-attributes) with no attribute groups, enough
+    /// to exercise status-code parsing and success classification.
+    fn response_with_status(status_code: u16) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend([0x01, 0x01]); // version 1.1
+        body.extend(status_code.to_be_bytes());
+        body.extend(1u32.to_be_bytes()); // request-id
+        body.push(tag::END_OF_ATTRIBUTES);
+        body
+    }
+
+    #[test]
+    fn successful_ok_is_classified_as_success() {
+        let response = parse_response(&response_with_status(0x0000)).unwrap();
+        assert_eq!(response.status_code, 0x0000);
+        assert!(response.is_success());
+    }
+
+    #[test]
+    fn successful_ok_ignoring_subscribed_event_is_classified_as_success() {
+        let response = parse_response(&response_with_status(0x0001)).unwrap();
+        assert!(response.is_success());
+    }
+
+    #[test]
+    fn client_error_is_not_classified_as_success() {
+        // 0x0400 = client-error-bad-request
+        let response = parse_response(&response_with_status(0x0400)).unwrap();
+        assert!(!response.is_success());
+    }
+
+    #[test]
+    fn server_error_is_not_classified_as_success() {
+        // 0x0500 = server-error-internal-error
+        let response = parse_response(&response_with_status(0x0500)).unwrap();
+        assert!(!response.is_success());
+    }
+
+    #[test]
+    fn parse_response_extracts_job_id_and_state_from_job_group() {
+        let mut body = Vec::new();
+        body.extend([0x01, 0x01]);
+        body.extend(0x0000u16.to_be_bytes());
+        body.extend(1u32.to_be_bytes());
+        body.push(tag::JOB_ATTRIBUTES);
+        write_attribute(&mut body, tag::INTEGER, "job-id", &42i32.to_be_bytes());
+        write_attribute(&mut body, tag::ENUM, "job-state", &3i32.to_be_bytes());
+        body.push(tag::END_OF_ATTRIBUTES);
+
+        let response = parse_response(&body).unwrap();
+        assert_eq!(response.job_id.as_deref(), Some("42"));
+        assert_eq!(response.job_state, Some(3));
+    }
+}
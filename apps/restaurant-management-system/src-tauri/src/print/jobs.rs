@@ -0,0 +1,227 @@
+//! Print-job status tracking and cancellation.
+//!
+//! `PrintResult.job_id` is only useful if callers can later ask "did
+//! it actually print?" or cancel a job stuck behind an offline
+//! printer — important for a POS during a dinner rush.
+
+use super::exec::{self, DEFAULT_QUERY_TIMEOUT_MS};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+/// Lifecycle state of a print job.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Pending,
+    Processing,
+    Completed,
+    Aborted,
+    Canceled,
+}
+
+/// Status of a single print job.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobStatus {
+    pub state: JobState,
+    pub message: Option<String>,
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+pub async fn get_job_status_unix(printer: &str, job_id: &str) -> Result<JobStatus, String> {
+    let mut not_completed_cmd = Command::new("lpstat");
+    not_completed_cmd.args(["-W", "not-completed", "-o", printer]);
+    let not_completed = exec::exec_checked(not_completed_cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map_err(|e| format!("Failed to query job status: {}", e))?;
+
+    let pending_stdout = String::from_utf8_lossy(&not_completed);
+    for (index, line) in pending_stdout.lines().enumerate() {
+        if line.split_whitespace().next() != Some(job_id) {
+            continue;
+        }
+        let state = if index == 0 {
+            JobState::Processing
+        } else {
+            JobState::Pending
+        };
+        return Ok(JobStatus {
+            state,
+            message: Some(line.trim().to_string()),
+        });
+    }
+
+    let mut completed_cmd = Command::new("lpstat");
+    completed_cmd.args(["-W", "completed", "-o", printer]);
+    let completed = exec::exec_checked(completed_cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map_err(|e| format!("Failed to query job status: {}", e))?;
+
+    let completed_stdout = String::from_utf8_lossy(&completed);
+    for line in completed_stdout.lines() {
+        if line.split_whitespace().next() != Some(job_id) {
+            continue;
+        }
+        return Ok(JobStatus {
+            state: JobState::Completed,
+            message: Some(line.trim().to_string()),
+        });
+    }
+
+    Err(format!("No job {} found for printer {}", job_id, printer))
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+pub async fn cancel_job_unix(job_id: &str) -> Result<(), String> {
+    let mut cmd = Command::new("cancel");
+    cmd.arg(job_id);
+    exec::exec_checked(cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to cancel job: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+pub async fn get_job_status_windows(printer: &str, job_id: &str) -> Result<JobStatus, String> {
+    let printer = exec::escape_powershell_arg(printer);
+    let job_id = exec::escape_powershell_arg(job_id);
+    let script = format!(
+        r#"Get-PrintJob -PrinterName "{printer}" -ID "{job_id}" | Select-Object JobStatus | ConvertTo-Json"#,
+        printer = printer,
+        job_id = job_id
+    );
+
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-Command", &script]);
+    let output = exec::exec_checked(cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map_err(|e| format!("Failed to query job status: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output);
+    if stdout.trim().is_empty() {
+        return Err(format!("No job {} found for printer {}", job_id, printer));
+    }
+
+    #[derive(Deserialize)]
+    struct WinJobStatus {
+        #[serde(rename = "JobStatus")]
+        job_status: String,
+    }
+
+    let parsed: WinJobStatus = serde_json::from_str(&stdout)
+        .map_err(|e| format!("Failed to parse job status: {} - Output: {}", e, stdout))?;
+
+    Ok(JobStatus {
+        state: classify_win_job_status(&parsed.job_status),
+        message: Some(parsed.job_status),
+    })
+}
+
+/// Map a `Get-PrintJob` `JobStatus` string to a [`JobState`].
+///
+/// `JobStatus` is a comma-separated combination of PrintJobStatus flags
+/// (e.g. `"Printing"`, `"Error, Offline"`), so this takes the most
+/// specific state recognized rather than matching the whole string.
+/// Kept free of the `windows` cfg gate (unlike the command that
+/// produces its input) so the classification logic can be unit tested
+/// on any platform.
+fn classify_win_job_status(job_status: &str) -> JobState {
+    if job_status.contains("Completed") || job_status.contains("Printed") {
+        JobState::Completed
+    } else if job_status.contains("Deleting") || job_status.contains("Deleted") {
+        JobState::Canceled
+    } else if job_status.contains("Error") || job_status.contains("Blocked") {
+        JobState::Aborted
+    } else if job_status.contains("Printing") {
+        JobState::Processing
+    } else {
+        JobState::Pending
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub async fn cancel_job_windows(printer: &str, job_id: &str) -> Result<(), String> {
+    let printer = exec::escape_powershell_arg(printer);
+    let job_id = exec::escape_powershell_arg(job_id);
+    let script = format!(
+        r#"Get-PrintJob -PrinterName "{printer}" -ID "{job_id}" | Remove-PrintJob"#,
+        printer = printer,
+        job_id = job_id
+    );
+
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-Command", &script]);
+    exec::exec_checked(cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to cancel job: {}", e))
+}
+
+/// Look up the most recently submitted job on `printer`. Used right
+/// after submitting a print job so callers get back an id to poll
+/// ([`get_job_status_windows`]) or cancel, even though none of the
+/// Windows print commands report one directly.
+///
+/// This only identifies the right job if no other caller submits a job
+/// to the same printer between the print and this lookup; the only
+/// current caller (`print_html_windows`) holds a lock across both for
+/// that reason.
+#[cfg(target_os = "windows")]
+pub async fn latest_job_id_windows(printer: &str) -> Result<Option<String>, String> {
+    let printer = exec::escape_powershell_arg(printer);
+    let script = format!(
+        r#"Get-PrintJob -PrinterName "{printer}" | Sort-Object -Property SubmittedTime -Descending | Select-Object -First 1 -ExpandProperty Id"#,
+        printer = printer
+    );
+
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-Command", &script]);
+    let output = exec::exec_checked(cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map_err(|e| format!("Failed to look up submitted job: {}", e))?;
+
+    let id = String::from_utf8_lossy(&output).trim().to_string();
+    Ok(if id.is_empty() { None } else { Some(id) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_printing_as_processing() {
+        assert_eq!(classify_win_job_status("Printing"), JobState::Processing);
+    }
+
+    #[test]
+    fn classifies_completed_and_printed_as_completed() {
+        assert_eq!(classify_win_job_status("Completed"), JobState::Completed);
+        assert_eq!(classify_win_job_status("Printed"), JobState::Completed);
+    }
+
+    #[test]
+    fn classifies_deleting_and_deleted_as_canceled() {
+        assert_eq!(classify_win_job_status("Deleting"), JobState::Canceled);
+        assert_eq!(classify_win_job_status("Deleted"), JobState::Canceled);
+    }
+
+    #[test]
+    fn classifies_error_and_blocked_as_aborted() {
+        assert_eq!(classify_win_job_status("Error, Offline"), JobState::Aborted);
+        assert_eq!(classify_win_job_status("Blocked"), JobState::Aborted);
+    }
+
+    #[test]
+    fn classifies_unrecognized_status_as_pending() {
+        assert_eq!(classify_win_job_status("Offline"), JobState::Pending);
+    }
+
+    #[test]
+    fn most_specific_flag_wins_when_status_has_multiple_flags() {
+        // A job that errored out after it had already started printing
+        // should be reported as aborted, not processing.
+        assert_eq!(
+            classify_win_job_status("Printing, Error"),
+            JobState::Aborted
+        );
+    }
+}
@@ -0,0 +1,783 @@
+//! Print module for native printing support in Tauri.
+//!
+//! This module provides:
+//! - Printer discovery (list available printers)
+//! - Silent printing to thermal receipt printers
+//! - Direct ESC/POS printing over USB, bypassing the HTML/CUPS/IE path
+//! - Timeout-guarded command execution (see [`exec`]) so a hung or
+//!   offline printer can't block a command forever
+//! - Cross-platform support (macOS, Windows, Linux)
+
+mod capabilities;
+mod exec;
+mod ipp;
+mod jobs;
+mod receipt;
+mod usb;
+
+pub use capabilities::{MediaSize, PrinterCapabilities};
+pub use jobs::{JobState, JobStatus};
+pub use receipt::{Alignment, BarcodeKind, ReceiptDoc, ReceiptElement};
+pub use usb::UsbIdentity;
+
+use exec::{DEFAULT_PRINT_TIMEOUT_MS, DEFAULT_QUERY_TIMEOUT_MS};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use tempfile::NamedTempFile;
+use tokio::process::Command;
+
+/// Information about an available printer.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrinterInfo {
+    /// Printer name (used for printing)
+    pub name: String,
+    /// Whether this is the default printer
+    pub is_default: bool,
+    /// Printer description or driver name
+    pub description: Option<String>,
+    /// Printer status (if available)
+    pub status: Option<String>,
+    /// USB identity, present when this printer was discovered by
+    /// scanning USB devices rather than the OS print spooler. Pass it
+    /// back via [`PrintOptions::usb`] to target this device directly.
+    #[serde(default)]
+    pub usb: Option<UsbIdentity>,
+    /// IPP URI (`ipp://host:631/...`), present when this printer was
+    /// discovered over DNS-SD/mDNS rather than the OS print spooler.
+    /// Pass it to [`print_ipp`] to print directly without a configured
+    /// CUPS queue.
+    #[serde(default)]
+    pub uri: Option<String>,
+}
+
+/// Result of a print operation.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrintResult {
+    /// Whether the print was successful
+    pub success: bool,
+    /// Error message if print failed
+    pub error: Option<String>,
+    /// Job ID if available (for tracking)
+    pub job_id: Option<String>,
+}
+
+/// Options for printing.
+#[derive(Debug, Deserialize)]
+pub struct PrintOptions {
+    /// Target printer name (uses default if not specified)
+    pub printer: Option<String>,
+    /// Number of copies to print
+    pub copies: Option<u32>,
+    /// Whether to print silently (no dialog)
+    pub silent: Option<bool>,
+    /// Paper width in mm (default: 80mm for thermal printers)
+    pub paper_width: Option<u32>,
+    /// Target a specific USB printer directly, bypassing the OS print
+    /// spooler. Required for [`print_escpos`] and [`print_receipt`]
+    /// when more than one USB printer is attached.
+    pub usb: Option<UsbIdentity>,
+    /// How long to wait for the underlying print command before
+    /// killing it and reporting a timeout, instead of hanging forever
+    /// on an offline/hung printer. Defaults to
+    /// [`exec::DEFAULT_PRINT_TIMEOUT_MS`].
+    pub timeout_ms: Option<u64>,
+}
+
+/// Get list of available printers.
+///
+/// Merges printers reported by the OS print spooler with printers
+/// discovered by scanning attached USB devices for a printer-class
+/// interface, so USB thermal printers show up even without a
+/// CUPS/Windows driver configured.
+///
+/// # Platform Support
+/// - macOS/Linux: Uses `lpstat -p -d` command
+/// - Windows: Uses PowerShell `Get-Printer` cmdlet
+#[tauri::command]
+pub async fn get_printers() -> Result<Vec<PrinterInfo>, String> {
+    let mut printers = {
+        #[cfg(target_os = "windows")]
+        {
+            get_printers_windows().await?
+        }
+
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        {
+            get_printers_unix().await?
+        }
+    };
+
+    let usb_printers = match tokio::task::spawn_blocking(usb::discover_usb_printers).await {
+        Ok(printers) => printers,
+        Err(e) => {
+            log::warn!("USB discovery task panicked: {}", e);
+            Vec::new()
+        }
+    };
+
+    for device in usb_printers {
+        let already_known = printers
+            .iter()
+            .any(|p| p.usb.as_ref().is_some_and(|u| *u == device.identity));
+        if already_known {
+            continue;
+        }
+
+        printers.push(PrinterInfo {
+            name: format!(
+                "USB {:04x}:{:04x}",
+                device.identity.vendor_id, device.identity.product_id
+            ),
+            is_default: false,
+            description: device.description,
+            status: None,
+            usb: Some(device.identity),
+            uri: None,
+        });
+    }
+
+    let ipp_printers = match tokio::task::spawn_blocking(ipp::discover_ipp_printers).await {
+        Ok(printers) => printers,
+        Err(e) => {
+            log::warn!("IPP discovery task panicked: {}", e);
+            Vec::new()
+        }
+    };
+
+    for device in ipp_printers {
+        let already_known = printers.iter().any(|p| p.uri.as_deref() == Some(&device.uri));
+        if already_known {
+            continue;
+        }
+
+        printers.push(PrinterInfo {
+            name: device.name,
+            is_default: false,
+            description: device.description,
+            status: None,
+            usb: None,
+            uri: Some(device.uri),
+        });
+    }
+
+    Ok(printers)
+}
+
+/// Print raw data directly to a network printer over IPP.
+///
+/// Builds an IPP `Print-Job` request (operation-attributes group with
+/// `printer-uri`, `requesting-user-name`, `document-format`, then the
+/// document bytes) and POSTs it to `uri`. Works identically across
+/// platforms since it doesn't depend on any OS print command. Honors
+/// [`PrintOptions::timeout_ms`] so an offline/unreachable network
+/// printer fails fast instead of hanging the command.
+///
+/// `format` is the IPP `document-format` MIME type, e.g.
+/// `application/octet-stream` for raw ESC/POS or `text/html`.
+#[tauri::command]
+pub async fn print_ipp(
+    uri: String,
+    data: Vec<u8>,
+    format: String,
+    options: Option<PrintOptions>,
+) -> Result<PrintResult, String> {
+    let timeout_ms = options
+        .and_then(|o| o.timeout_ms)
+        .unwrap_or(DEFAULT_PRINT_TIMEOUT_MS);
+
+    match ipp::print_ipp(&uri, &data, &format, "origin-food-house", timeout_ms).await {
+        Ok(response) if response.is_success() => Ok(PrintResult {
+            success: true,
+            error: None,
+            job_id: response.job_id,
+        }),
+        Ok(response) => Ok(PrintResult {
+            success: false,
+            error: Some(format!("IPP request failed with status 0x{:04x}", response.status_code)),
+            job_id: response.job_id,
+        }),
+        Err(e) => Ok(PrintResult {
+            success: false,
+            error: Some(e),
+            job_id: None,
+        }),
+    }
+}
+
+/// Query the media sizes, resolutions, and color/duplex support a
+/// printer's driver actually advertises, so the UI can offer only
+/// valid paper widths instead of hardcoding 80mm/58mm.
+///
+/// # Platform Support
+/// - macOS/Linux: Parses `lpoptions -p <printer> -l`
+/// - Windows: Uses `Get-PrintConfiguration` / `Get-PrinterProperty`
+#[tauri::command]
+pub async fn get_printer_capabilities(printer: String) -> Result<PrinterCapabilities, String> {
+    #[cfg(target_os = "windows")]
+    {
+        capabilities::get_printer_capabilities_windows(&printer).await
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        capabilities::get_printer_capabilities_unix(&printer).await
+    }
+}
+
+/// Check whether a print job has actually finished printing.
+///
+/// Lets the POS show a spinner until the ticket actually prints rather
+/// than just until the spooler accepts it.
+///
+/// # Platform Support
+/// - macOS/Linux: Polls `lpstat -W not-completed/-completed -o <printer>`
+/// - Windows: Uses `Get-PrintJob -PrinterName ... -ID ...`
+#[tauri::command]
+pub async fn get_job_status(printer: String, job_id: String) -> Result<JobStatus, String> {
+    #[cfg(target_os = "windows")]
+    {
+        jobs::get_job_status_windows(&printer, &job_id).await
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        jobs::get_job_status_unix(&printer, &job_id).await
+    }
+}
+
+/// Cancel a print job, e.g. one stuck behind an offline printer.
+///
+/// # Platform Support
+/// - macOS/Linux: Uses `cancel <job_id>`
+/// - Windows: Uses `Get-PrintJob ... | Remove-PrintJob`
+#[tauri::command]
+pub async fn cancel_job(printer: String, job_id: String) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        jobs::cancel_job_windows(&printer, &job_id).await
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        let _ = printer;
+        jobs::cancel_job_unix(&job_id).await
+    }
+}
+
+/// Print raw ESC/POS command bytes directly to a USB thermal printer.
+///
+/// Enumerates USB devices exposing a printer-class interface
+/// (`bInterfaceClass == 7`), matches the one identified by
+/// [`PrintOptions::usb`] (or the first one found if unset), claims its
+/// bulk-OUT endpoint, and writes `commands` with a write timeout.
+/// [`PrintOptions::copies`] is honored by repeating the write, since a
+/// raw ESC/POS stream has no spooler-level copies concept to hand off
+/// to.
+#[tauri::command]
+pub async fn print_escpos(
+    commands: Vec<u8>,
+    options: Option<PrintOptions>,
+) -> Result<PrintResult, String> {
+    let opts = options.unwrap_or(PrintOptions {
+        printer: None,
+        copies: Some(1),
+        silent: Some(true),
+        paper_width: Some(80),
+        usb: None,
+        timeout_ms: None,
+    });
+
+    let identity = match opts.usb {
+        Some(identity) => identity,
+        None => tokio::task::spawn_blocking(usb::discover_usb_printers)
+            .await
+            .map_err(|e| format!("USB discovery task panicked: {}", e))?
+            .into_iter()
+            .next()
+            .map(|d| d.identity)
+            .ok_or_else(|| "No USB printer found".to_string())?,
+    };
+
+    let timeout_ms = opts.timeout_ms.unwrap_or(DEFAULT_PRINT_TIMEOUT_MS);
+    let copies = opts.copies.unwrap_or(1).max(1);
+
+    for _ in 0..copies {
+        let identity = identity.clone();
+        let commands = commands.clone();
+        let result = tokio::task::spawn_blocking(move || usb::write_bytes(&identity, &commands, timeout_ms))
+            .await
+            .map_err(|e| format!("USB write task panicked: {}", e))?;
+
+        if let Err(e) = result {
+            return Ok(PrintResult {
+                success: false,
+                error: Some(e),
+                job_id: None,
+            });
+        }
+    }
+
+    Ok(PrintResult {
+        success: true,
+        error: None,
+        job_id: None,
+    })
+}
+
+/// Build a receipt from high-level elements (text, cuts, barcodes,
+/// cash-drawer kicks, ...) and print it directly over USB.
+///
+/// This is the high-level counterpart to [`print_escpos`]: it compiles
+/// `receipt` down to ESC/POS bytes via [`receipt::build_escpos`] and
+/// sends them the same way.
+#[tauri::command]
+pub async fn print_receipt(
+    receipt: ReceiptDoc,
+    options: Option<PrintOptions>,
+) -> Result<PrintResult, String> {
+    let commands = receipt::build_escpos(&receipt)?;
+    print_escpos(commands, options).await
+}
+
+/// Print HTML content to a printer.
+///
+/// The HTML is written to a temporary file and printed using OS-specific commands.
+///
+/// # Arguments
+/// * `html` - The HTML content to print
+/// * `options` - Print options (printer, copies, etc.)
+///
+/// # Platform Support
+/// - macOS/Linux: Uses `lp` command with CUPS
+/// - Windows: Uses HTML to PDF conversion then prints
+#[tauri::command]
+pub async fn print_html(html: String, options: Option<PrintOptions>) -> Result<PrintResult, String> {
+    let opts = options.unwrap_or(PrintOptions {
+        printer: None,
+        copies: Some(1),
+        silent: Some(true),
+        paper_width: Some(80),
+        usb: None,
+        timeout_ms: None,
+    });
+
+    #[cfg(target_os = "windows")]
+    {
+        print_html_windows(&html, &opts).await
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        print_html_unix(&html, &opts).await
+    }
+}
+
+// ============================================================================
+// Unix (macOS/Linux) Implementation
+// ============================================================================
+
+/// Name of the system default printer (`lpstat -d`'s `system default
+/// destination: <name>` line), or `None` if nothing is set as default.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+async fn default_printer_name_unix() -> Result<Option<String>, String> {
+    let mut cmd = Command::new("lpstat");
+    cmd.args(["-d"]);
+    let output = exec::exec(cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map_err(|e| format!("Failed to get default printer: {}", e))?;
+
+    let name = String::from_utf8_lossy(&output.stdout)
+        .split(':')
+        .nth(1)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    Ok(name)
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+async fn get_printers_unix() -> Result<Vec<PrinterInfo>, String> {
+    let default_printer = default_printer_name_unix().await?.unwrap_or_default();
+
+    // Get all printers
+    let mut list_cmd = Command::new("lpstat");
+    list_cmd.args(["-p"]);
+    let output = exec::exec(list_cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map_err(|e| format!("Failed to list printers: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut printers: Vec<PrinterInfo> = Vec::new();
+
+    for line in stdout.lines() {
+        // Parse lines like: "printer PrinterName is idle.  enabled since..."
+        if line.starts_with("printer ") {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 2 {
+                let name = parts[1].to_string();
+                let is_default = name == default_printer;
+                let status = if line.contains("idle") {
+                    Some("idle".to_string())
+                } else if line.contains("printing") {
+                    Some("printing".to_string())
+                } else {
+                    None
+                };
+
+                printers.push(PrinterInfo {
+                    name: name.clone(),
+                    is_default,
+                    description: None,
+                    status,
+                    usb: None,
+                    uri: None,
+                });
+            }
+        }
+    }
+
+    // If no printers found via lpstat, try lpinfo
+    if printers.is_empty() {
+        log::info!("No printers found via lpstat, this may indicate no printers are configured");
+    }
+
+    Ok(printers)
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+async fn print_html_unix(html: &str, options: &PrintOptions) -> Result<PrintResult, String> {
+    // Create a temporary HTML file
+    let mut temp_file = NamedTempFile::new()
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+
+    // Write HTML content
+    temp_file
+        .write_all(html.as_bytes())
+        .map_err(|e| format!("Failed to write HTML to temp file: {}", e))?;
+
+    // Build lp command arguments
+    let mut args: Vec<String> = Vec::new();
+
+    // Specify printer if provided
+    if let Some(ref printer) = options.printer {
+        args.push("-d".to_string());
+        args.push(printer.clone());
+    }
+
+    // Number of copies
+    let copies = options.copies.unwrap_or(1);
+    if copies > 1 {
+        args.push("-n".to_string());
+        args.push(copies.to_string());
+    }
+
+    // Set media size for thermal paper. Prefer a media name the printer
+    // actually reports support for over guessing a CUPS custom-media
+    // name, since an unsupported name makes `lp` fall back silently to
+    // the printer's default page size. When no printer was specified,
+    // resolve the system default so the common "just print to whatever
+    // thermal printer is hooked up" path still gets real capabilities
+    // instead of the guessed fallback.
+    let paper_width = options.paper_width.unwrap_or(80);
+    let target_printer = match &options.printer {
+        Some(printer) => Some(printer.clone()),
+        None => default_printer_name_unix().await.unwrap_or(None),
+    };
+    let media_name = match &target_printer {
+        Some(printer) => match capabilities::get_printer_capabilities_unix(printer).await {
+            Ok(caps) => caps
+                .closest_media_by_width(paper_width)
+                .map(|m| m.name.clone())
+                .unwrap_or_else(|| format!("Custom.{}x200mm", paper_width)),
+            Err(e) => {
+                log::warn!("Failed to query printer capabilities, falling back to guessed media name: {}", e);
+                format!("Custom.{}x200mm", paper_width)
+            }
+        },
+        None => format!("Custom.{}x200mm", paper_width),
+    };
+    args.push("-o".to_string());
+    args.push(format!("media={}", media_name));
+
+    // Set print options for thermal printers
+    args.push("-o".to_string());
+    args.push("fit-to-page".to_string());
+
+    // Add the file path
+    args.push(temp_file.path().to_string_lossy().to_string());
+
+    // Execute lp command
+    log::info!("Executing lp with args: {:?}", args);
+    let mut lp_cmd = Command::new("lp");
+    lp_cmd.args(&args);
+    let timeout_ms = options.timeout_ms.unwrap_or(DEFAULT_PRINT_TIMEOUT_MS);
+
+    match exec::exec(lp_cmd, timeout_ms).await {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            // Try to extract job ID from output like "request id is PrinterName-123 (1 file(s))"
+            let job_id = stdout
+                .split("request id is ")
+                .nth(1)
+                .and_then(|s| s.split_whitespace().next())
+                .map(|s| s.to_string());
+
+            Ok(PrintResult {
+                success: true,
+                error: None,
+                job_id,
+            })
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Ok(PrintResult {
+                success: false,
+                error: Some(stderr.to_string()),
+                job_id: None,
+            })
+        }
+        Err(e) => Ok(PrintResult {
+            success: false,
+            error: Some(e.to_string()),
+            job_id: None,
+        }),
+    }
+}
+
+// ============================================================================
+// Windows Implementation
+// ============================================================================
+
+#[cfg(target_os = "windows")]
+async fn get_printers_windows() -> Result<Vec<PrinterInfo>, String> {
+    // Use PowerShell to get printer list
+    let mut cmd = Command::new("powershell");
+    cmd.args([
+        "-Command",
+        "Get-Printer | Select-Object Name, DriverName, Default | ConvertTo-Json",
+    ]);
+    let output = exec::exec_checked(cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map_err(|e| format!("Failed to get printers: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output);
+
+    // Handle case where PowerShell returns nothing or single object (not array)
+    if stdout.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Try to parse as array first, then as single object
+    #[derive(Deserialize)]
+    struct WinPrinter {
+        #[serde(rename = "Name")]
+        name: String,
+        #[serde(rename = "DriverName")]
+        driver_name: Option<String>,
+        #[serde(rename = "Default")]
+        default: Option<bool>,
+    }
+
+    let win_printers: Vec<WinPrinter> = serde_json::from_str(&stdout)
+        .or_else(|_| {
+            // Try parsing as single object
+            serde_json::from_str::<WinPrinter>(&stdout).map(|p| vec![p])
+        })
+        .map_err(|e| format!("Failed to parse printer list: {} - Output: {}", e, stdout))?;
+
+    let printers = win_printers
+        .into_iter()
+        .map(|p| PrinterInfo {
+            name: p.name,
+            is_default: p.default.unwrap_or(false),
+            description: p.driver_name,
+            status: None,
+            usb: None,
+            uri: None,
+        })
+        .collect();
+
+    Ok(printers)
+}
+
+/// Name of the printer `Get-Printer` reports as default, or `None` if
+/// nothing is set as default.
+#[cfg(target_os = "windows")]
+async fn default_printer_name_windows() -> Result<Option<String>, String> {
+    let mut cmd = Command::new("powershell");
+    cmd.args([
+        "-Command",
+        "Get-Printer | Where-Object Default | Select-Object -ExpandProperty Name",
+    ]);
+    let output = exec::exec_checked(cmd, DEFAULT_QUERY_TIMEOUT_MS)
+        .await
+        .map_err(|e| format!("Failed to get default printer: {}", e))?;
+
+    let name = String::from_utf8_lossy(&output).trim().to_string();
+    Ok(if name.is_empty() { None } else { Some(name) })
+}
+
+/// Serializes [`print_html_windows`] calls.
+///
+/// That function temporarily swaps the machine-wide Windows default
+/// printer to target a specific printer via IE's COM automation (which
+/// has no "print to printer X" option of its own), then resolves the
+/// job id it was assigned by asking for the most recently submitted job
+/// on that printer. Both steps read/mutate state that isn't scoped to
+/// one call, so two concurrent `print_html` calls for different
+/// printers — normal during a rush — can interleave: one call's
+/// "restore previous default" can clobber the other's still-in-flight
+/// default, and `latest_job_id_windows` can return the other call's job
+/// id. Holding this lock for the whole function serializes the default
+/// printer swap and job-id lookup so each call's lookup sees only its
+/// own submission.
+#[cfg(target_os = "windows")]
+static WINDOWS_HTML_PRINT_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[cfg(target_os = "windows")]
+async fn print_html_windows(html: &str, options: &PrintOptions) -> Result<PrintResult, String> {
+    use std::os::windows::process::CommandExt;
+
+    let _guard = WINDOWS_HTML_PRINT_LOCK.lock().await;
+
+    // Create a temporary HTML file
+    let mut temp_file = NamedTempFile::with_suffix(".html")
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+
+    // Add print-specific CSS for thermal printers
+    let paper_width = options.paper_width.unwrap_or(80);
+    let styled_html = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<style>
+@page {{
+    size: {}mm auto;
+    margin: 0;
+}}
+@media print {{
+    body {{
+        width: {}mm;
+        margin: 0;
+        padding: 2mm;
+    }}
+}}
+</style>
+</head>
+<body>
+{}
+</body>
+</html>"#,
+        paper_width, paper_width, html
+    );
+
+    temp_file
+        .write_all(styled_html.as_bytes())
+        .map_err(|e| format!("Failed to write HTML to temp file: {}", e))?;
+
+    let file_path = temp_file.path().to_string_lossy().to_string();
+
+    // On Windows, we use PowerShell to print HTML via default browser's print function
+    // or use print verb on the file
+    let copies = options.copies.unwrap_or(1);
+
+    // Resolve which queue the job lands on so we can look up the job id
+    // it was assigned after submission (neither the IE COM script nor
+    // the `Start-Process -Verb Print` path reports one directly).
+    let target_printer = match &options.printer {
+        Some(printer) => Some(printer.clone()),
+        None => default_printer_name_windows().await.unwrap_or(None),
+    };
+
+    let print_script = if let Some(ref printer) = options.printer {
+        // `$ie.ExecWB(6, 2)` always prints via IE's own default printer,
+        // it has no "print to printer X" option. So to actually honor
+        // `printer`, switch the Windows default to it for the duration
+        // of the print and restore whatever was default before in a
+        // `finally` block, so a failed print still restores it. If the
+        // process is killed on timeout (see below), that block does not
+        // run and the default stays switched.
+        let printer_escaped = exec::escape_powershell_arg(printer);
+        format!(
+            r#"
+            $printerName = "{printer}"
+            $originalDefault = (Get-CimInstance -Class Win32_Printer | Where-Object {{ $_.Default }}).Name
+            $target = Get-CimInstance -Class Win32_Printer | Where-Object {{ $_.Name -eq $printerName }}
+            if ($target) {{ Invoke-CimMethod -InputObject $target -MethodName SetDefaultPrinter | Out-Null }}
+            try {{
+                $ie = New-Object -ComObject InternetExplorer.Application
+                $ie.Visible = $false
+                $ie.Navigate("{file_path}")
+                while ($ie.Busy) {{ Start-Sleep -Milliseconds 100 }}
+                for ($i = 0; $i -lt {copies}; $i++) {{
+                    $ie.ExecWB(6, 2)
+                }}
+                Start-Sleep -Seconds 2
+                $ie.Quit()
+            }} finally {{
+                if ($originalDefault -and $originalDefault -ne $printerName) {{
+                    $restore = Get-CimInstance -Class Win32_Printer | Where-Object {{ $_.Name -eq $originalDefault }}
+                    if ($restore) {{ Invoke-CimMethod -InputObject $restore -MethodName SetDefaultPrinter | Out-Null }}
+                }}
+            }}
+            "#,
+            printer = printer_escaped,
+            file_path = file_path.replace("\\", "\\\\"),
+            copies = copies
+        )
+    } else {
+        // Use default print behavior
+        format!(
+            r#"
+            Start-Process -FilePath "{}" -Verb Print -Wait
+            "#,
+            file_path.replace("\\", "\\\\")
+        )
+    };
+
+    // CREATE_NO_WINDOW flag
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-Command", &print_script])
+        .creation_flags(CREATE_NO_WINDOW);
+
+    // The fixed `Start-Sleep -Seconds 2` inside the IE COM script can't
+    // be made deterministic on its own, but wrapping the whole
+    // invocation in `exec` means the PowerShell process running it is
+    // killed once the timeout elapses, instead of blocking the command
+    // indefinitely. The IE instance it started over COM runs in its own
+    // process and is not killed with it.
+    let timeout_ms = options.timeout_ms.unwrap_or(DEFAULT_PRINT_TIMEOUT_MS);
+
+    match exec::exec(cmd, timeout_ms).await {
+        Ok(output) if output.status.success() => {
+            let job_id = match &target_printer {
+                Some(printer) => jobs::latest_job_id_windows(printer).await.unwrap_or(None),
+                None => None,
+            };
+            Ok(PrintResult {
+                success: true,
+                error: None,
+                job_id,
+            })
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Ok(PrintResult {
+                success: false,
+                error: Some(if stderr.is_empty() {
+                    "Print command failed".to_string()
+                } else {
+                    stderr.to_string()
+                }),
+                job_id: None,
+            })
+        }
+        Err(e) => Ok(PrintResult {
+            success: false,
+            error: Some(e.to_string()),
+            job_id: None,
+        }),
+    }
+}
@@ -0,0 +1,347 @@
+//! `ReceiptDoc` -> ESC/POS byte stream builder.
+//!
+//! Lets callers describe a receipt as a sequence of high-level
+//! elements (text, cuts, barcodes, ...) instead of hand-assembling
+//! ESC/POS command bytes, and compiles it down to the raw bytes that
+//! [`super::usb::write_bytes`] / `print_escpos` write to the printer.
+
+use serde::Deserialize;
+
+/// Horizontal alignment, mapped to ESC/POS `ESC a n`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Alignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl Alignment {
+    fn code(self) -> u8 {
+        match self {
+            Alignment::Left => 0,
+            Alignment::Center => 1,
+            Alignment::Right => 2,
+        }
+    }
+}
+
+/// 1D barcode symbology, mapped to the `m` byte of the length-prefixed
+/// (`GS k m n d1...dn`, "Format B") form of `GS k` that [`build_escpos`]
+/// emits.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum BarcodeKind {
+    Upca,
+    Upce,
+    Ean13,
+    Ean8,
+    Code39,
+    Itf,
+    Codabar,
+    Code93,
+    Code128,
+}
+
+impl BarcodeKind {
+    /// Format B values (65-73): Format A's `m` (0-8) instead selects the
+    /// NUL-terminated encoding with no length byte, which isn't what
+    /// [`build_escpos`] writes, and Format A has no value for
+    /// Code93/Code128 at all.
+    fn code(self) -> u8 {
+        match self {
+            BarcodeKind::Upca => 65,
+            BarcodeKind::Upce => 66,
+            BarcodeKind::Ean13 => 67,
+            BarcodeKind::Ean8 => 68,
+            BarcodeKind::Code39 => 69,
+            BarcodeKind::Itf => 70,
+            BarcodeKind::Codabar => 71,
+            BarcodeKind::Code93 => 72,
+            BarcodeKind::Code128 => 73,
+        }
+    }
+}
+
+/// One line item in a [`ReceiptDoc`].
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReceiptElement {
+    /// A line of text, optionally bold/aligned/scaled.
+    Text {
+        content: String,
+        #[serde(default)]
+        bold: bool,
+        #[serde(default)]
+        align: Option<Alignment>,
+        /// Character width multiplier, 1-8 (`GS ! n` high nibble).
+        #[serde(default)]
+        width: Option<u8>,
+        /// Character height multiplier, 1-8 (`GS ! n` low nibble).
+        #[serde(default)]
+        height: Option<u8>,
+    },
+    /// Feed `lines` blank lines.
+    Feed { lines: u8 },
+    /// Full cut (`GS V 0`).
+    Cut,
+    /// Pulse the cash-drawer kick-out connector (`ESC p 0 t1 t2`).
+    CashDrawer {
+        #[serde(default = "default_drawer_pulse")]
+        t1: u8,
+        #[serde(default = "default_drawer_pulse")]
+        t2: u8,
+    },
+    /// A 1D barcode (`GS k`).
+    Barcode { kind: BarcodeKind, data: String },
+    /// A QR code (`GS ( k`).
+    Qr {
+        data: String,
+        #[serde(default = "default_qr_size")]
+        size: u8,
+        #[serde(default = "default_qr_ec_level")]
+        error_correction: u8,
+    },
+}
+
+fn default_drawer_pulse() -> u8 {
+    120
+}
+
+fn default_qr_size() -> u8 {
+    6
+}
+
+fn default_qr_ec_level() -> u8 {
+    // 0x31 = level M, the common default for receipt QR codes.
+    0x31
+}
+
+/// A full receipt, rendered top to bottom by [`build_escpos`].
+#[derive(Debug, Deserialize)]
+pub struct ReceiptDoc {
+    pub elements: Vec<ReceiptElement>,
+}
+
+/// Largest payload `GS k` can encode, since the length prefix is a
+/// single byte.
+const MAX_BARCODE_LEN: usize = 255;
+
+/// Largest payload `GS ( k` "store data" can encode, since the `pl`/`ph`
+/// length prefix is two bytes wide (and 3 of those bytes are the
+/// function-code header baked into `data_len` below).
+const MAX_QR_DATA_LEN: usize = 0xFFFF - 3;
+
+/// Compile a [`ReceiptDoc`] into an ESC/POS byte stream, starting with
+/// a printer init (`ESC @`).
+///
+/// Returns an error instead of emitting a command if an element's
+/// payload can't be represented, e.g. a barcode/QR `data` too long for
+/// its length prefix — encoding it anyway would truncate the prefix
+/// while still writing the full payload, corrupting the command stream
+/// for everything after it.
+pub fn build_escpos(doc: &ReceiptDoc) -> Result<Vec<u8>, String> {
+    let mut out = vec![0x1B, 0x40]; // ESC @ : initialize printer
+
+    for element in &doc.elements {
+        match element {
+            ReceiptElement::Text {
+                content,
+                bold,
+                align,
+                width,
+                height,
+            } => {
+                let align_code = align.map(Alignment::code).unwrap_or(Alignment::Left.code());
+                out.extend([0x1B, b'a', align_code]);
+                out.extend([0x1B, b'E', if *bold { 1 } else { 0 }]);
+                if width.is_some() || height.is_some() {
+                    let w = width.unwrap_or(1).clamp(1, 8) - 1;
+                    let h = height.unwrap_or(1).clamp(1, 8) - 1;
+                    out.extend([0x1D, b'!', (w << 4) | h]);
+                }
+                out.extend(content.as_bytes());
+                out.push(b'\n');
+                if width.is_some() || height.is_some() {
+                    out.extend([0x1D, b'!', 0x00]);
+                }
+                if *bold {
+                    out.extend([0x1B, b'E', 0]);
+                }
+                // Alignment, like bold, is printer-persistent state
+                // (not reset by feed/newline), so normalize it back to
+                // the default after every element instead of leaking
+                // it into whatever comes next.
+                out.extend([0x1B, b'a', Alignment::Left.code()]);
+            }
+            ReceiptElement::Feed { lines } => {
+                out.extend([0x1B, b'd', *lines]);
+            }
+            ReceiptElement::Cut => {
+                out.extend([0x1D, b'V', 0x00]);
+            }
+            ReceiptElement::CashDrawer { t1, t2 } => {
+                out.extend([0x1B, b'p', 0x00, *t1, *t2]);
+            }
+            ReceiptElement::Barcode { kind, data } => {
+                if data.len() > MAX_BARCODE_LEN {
+                    return Err(format!(
+                        "Barcode data is {} bytes, exceeds the GS k limit of {}",
+                        data.len(),
+                        MAX_BARCODE_LEN
+                    ));
+                }
+                out.extend([0x1D, b'k', kind.code()]);
+                out.push(data.len() as u8);
+                out.extend(data.as_bytes());
+            }
+            ReceiptElement::Qr {
+                data,
+                size,
+                error_correction,
+            } => {
+                if data.len() > MAX_QR_DATA_LEN {
+                    return Err(format!(
+                        "QR data is {} bytes, exceeds the GS ( k store-data limit of {}",
+                        data.len(),
+                        MAX_QR_DATA_LEN
+                    ));
+                }
+                // Select QR model 2.
+                out.extend([0x1D, b'(', b'k', 0x04, 0x00, 0x31, 0x41, 0x32, 0x00]);
+                // Set module size.
+                out.extend([0x1D, b'(', b'k', 0x03, 0x00, 0x31, 0x43, *size]);
+                // Set error-correction level.
+                out.extend([0x1D, b'(', b'k', 0x03, 0x00, 0x31, 0x45, *error_correction]);
+                // Store data.
+                let data_len = data.len() + 3;
+                let pl = (data_len & 0xFF) as u8;
+                let ph = ((data_len >> 8) & 0xFF) as u8;
+                out.extend([0x1D, b'(', b'k', pl, ph, 0x31, 0x50, 0x30]);
+                out.extend(data.as_bytes());
+                // Print the symbol.
+                out.extend([0x1D, b'(', b'k', 0x03, 0x00, 0x31, 0x51, 0x30]);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(content: &str) -> ReceiptElement {
+        ReceiptElement::Text {
+            content: content.to_string(),
+            bold: false,
+            align: None,
+            width: None,
+            height: None,
+        }
+    }
+
+    #[test]
+    fn alignment_does_not_leak_into_later_text_elements() {
+        let doc = ReceiptDoc {
+            elements: vec![
+                ReceiptElement::Text {
+                    content: "STORE NAME".to_string(),
+                    bold: false,
+                    align: Some(Alignment::Center),
+                    width: None,
+                    height: None,
+                },
+                text("item 1"),
+            ],
+        };
+
+        let bytes = build_escpos(&doc).unwrap();
+
+        // ESC a 1 (center) ... "STORE NAME" ... ESC a 0 (reset) ... "item 1"
+        let reset = [0x1B, b'a', 0x00];
+        let item_pos = bytes.windows(6).position(|w| w == b"item 1").unwrap();
+        let last_reset_before_item = bytes[..item_pos]
+            .windows(3)
+            .rposition(|w| w == reset)
+            .expect("alignment must be reset before the next text element");
+        assert!(last_reset_before_item < item_pos);
+    }
+
+    #[test]
+    fn bold_is_reset_after_text_element() {
+        let doc = ReceiptDoc {
+            elements: vec![ReceiptElement::Text {
+                content: "bold line".to_string(),
+                bold: true,
+                align: None,
+                width: None,
+                height: None,
+            }],
+        };
+
+        let bytes = build_escpos(&doc).unwrap();
+        // The bold reset (ESC E 0) is followed by the alignment reset (ESC a 0).
+        assert_eq!(
+            bytes[bytes.len() - 6..],
+            [0x1B, b'E', 0x00, 0x1B, b'a', 0x00]
+        );
+    }
+
+    #[test]
+    fn barcode_m_byte_uses_format_b_numbering() {
+        let doc = ReceiptDoc {
+            elements: vec![ReceiptElement::Barcode {
+                kind: BarcodeKind::Code128,
+                data: "12345".to_string(),
+            }],
+        };
+
+        let bytes = build_escpos(&doc).unwrap();
+        // GS k m n d1...dn : the byte after `GS k` is `m`, which must be
+        // 73 (0x49, Format B's CODE128) not 8 (Format A numbering).
+        let gs_k_pos = bytes
+            .windows(2)
+            .position(|w| w == [0x1D, b'k'])
+            .expect("GS k must be present");
+        assert_eq!(bytes[gs_k_pos + 2], 0x49);
+    }
+
+    #[test]
+    fn barcode_over_255_bytes_is_rejected_instead_of_truncated() {
+        let doc = ReceiptDoc {
+            elements: vec![ReceiptElement::Barcode {
+                kind: BarcodeKind::Code128,
+                data: "x".repeat(256),
+            }],
+        };
+
+        assert!(build_escpos(&doc).is_err());
+    }
+
+    #[test]
+    fn barcode_at_255_bytes_is_accepted() {
+        let doc = ReceiptDoc {
+            elements: vec![ReceiptElement::Barcode {
+                kind: BarcodeKind::Code128,
+                data: "x".repeat(255),
+            }],
+        };
+
+        assert!(build_escpos(&doc).is_ok());
+    }
+
+    #[test]
+    fn qr_data_over_limit_is_rejected() {
+        let doc = ReceiptDoc {
+            elements: vec![ReceiptElement::Qr {
+                data: "x".repeat(MAX_QR_DATA_LEN + 1),
+                size: 6,
+                error_correction: 0x31,
+            }],
+        };
+
+        assert!(build_escpos(&doc).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Direct USB communication with thermal receipt printers.
+//!
+//! Bypasses the OS print spooler (CUPS/`lp` on Unix, the IE-automation
+//! path on Windows) and talks to the printer's bulk-OUT endpoint
+//! directly, which is both faster and more reliable for ESC/POS
+//! thermal printers than rendering HTML and shelling out.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// USB identity of a printer, used to target a specific device with
+/// [`write_bytes`] when more than one printer is attached.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UsbIdentity {
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub serial: Option<String>,
+}
+
+/// A printer discovered by scanning USB devices for a printer-class
+/// interface (`bInterfaceClass == 7`).
+#[derive(Debug, Clone)]
+pub struct UsbPrinterDevice {
+    pub identity: UsbIdentity,
+    pub description: Option<String>,
+}
+
+/// USB interface class code for printers (USB spec, base class 7).
+const PRINTER_INTERFACE_CLASS: u8 = 7;
+
+/// Default time to wait for the bulk write to complete before giving up.
+const DEFAULT_WRITE_TIMEOUT_MS: u64 = 5000;
+
+/// Enumerate attached USB devices and return the ones that expose a
+/// printer-class interface.
+pub fn discover_usb_printers() -> Vec<UsbPrinterDevice> {
+    let devices = match rusb::devices() {
+        Ok(devices) => devices,
+        Err(e) => {
+            log::warn!("Failed to enumerate USB devices: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut printers = Vec::new();
+
+    for device in devices.iter() {
+        let device_desc = match device.device_descriptor() {
+            Ok(desc) => desc,
+            Err(_) => continue,
+        };
+
+        let Ok(config_desc) = device.active_config_descriptor() else {
+            continue;
+        };
+
+        let is_printer = config_desc.interfaces().any(|interface| {
+            interface
+                .descriptors()
+                .any(|d| d.class_code() == PRINTER_INTERFACE_CLASS)
+        });
+
+        if !is_printer {
+            continue;
+        }
+
+        let serial = device
+            .open()
+            .ok()
+            .and_then(|handle| {
+                handle
+                    .read_serial_number_string_ascii(&device_desc, Duration::from_millis(200))
+                    .ok()
+            });
+
+        let description = format!(
+            "USB printer {:04x}:{:04x}",
+            device_desc.vendor_id(),
+            device_desc.product_id()
+        );
+
+        printers.push(UsbPrinterDevice {
+            identity: UsbIdentity {
+                vendor_id: device_desc.vendor_id(),
+                product_id: device_desc.product_id(),
+                serial,
+            },
+            description: Some(description),
+        });
+    }
+
+    printers
+}
+
+/// Whether `device` matches `identity`: vendor/product id always, plus
+/// the device's serial number when `identity.serial` is set. The
+/// serial check requires opening the device, so it's only done when a
+/// serial was actually given to match against, and two identical
+/// printer models attached at once need it to pick the right one.
+fn matches_identity<T: rusb::UsbContext>(device: &rusb::Device<T>, identity: &UsbIdentity) -> bool {
+    let Ok(desc) = device.device_descriptor() else {
+        return false;
+    };
+
+    if desc.vendor_id() != identity.vendor_id || desc.product_id() != identity.product_id {
+        return false;
+    }
+
+    match &identity.serial {
+        Some(expected_serial) => device
+            .open()
+            .ok()
+            .and_then(|handle| {
+                handle
+                    .read_serial_number_string_ascii(&desc, Duration::from_millis(200))
+                    .ok()
+            })
+            .is_some_and(|serial| serial == *expected_serial),
+        None => true,
+    }
+}
+
+/// Claim the printer's bulk-OUT endpoint and write `data` to it,
+/// failing if the write doesn't complete within `timeout_ms`.
+pub fn write_bytes(identity: &UsbIdentity, data: &[u8], timeout_ms: u64) -> Result<(), String> {
+    let devices = rusb::devices().map_err(|e| format!("Failed to enumerate USB devices: {}", e))?;
+
+    let device = devices
+        .iter()
+        .find(|device| matches_identity(device, identity))
+        .ok_or_else(|| {
+            format!(
+                "No USB printer found matching {:04x}:{:04x}",
+                identity.vendor_id, identity.product_id
+            )
+        })?;
+
+    let config_desc = device
+        .active_config_descriptor()
+        .map_err(|e| format!("Failed to read USB config descriptor: {}", e))?;
+
+    let (interface_number, out_endpoint) = config_desc
+        .interfaces()
+        .find_map(|interface| {
+            interface.descriptors().find_map(|d| {
+                if d.class_code() != PRINTER_INTERFACE_CLASS {
+                    return None;
+                }
+                d.endpoint_descriptors()
+                    .find(|e| {
+                        e.direction() == rusb::Direction::Out
+                            && e.transfer_type() == rusb::TransferType::Bulk
+                    })
+                    .map(|e| (interface.number(), e.address()))
+            })
+        })
+        .ok_or_else(|| "USB printer has no bulk-OUT endpoint".to_string())?;
+
+    let mut handle = device
+        .open()
+        .map_err(|e| format!("Failed to open USB printer: {}", e))?;
+
+    handle
+        .set_auto_detach_kernel_driver(true)
+        .ok();
+
+    handle
+        .claim_interface(interface_number)
+        .map_err(|e| format!("Failed to claim USB interface: {}", e))?;
+
+    let timeout = Duration::from_millis(if timeout_ms == 0 {
+        DEFAULT_WRITE_TIMEOUT_MS
+    } else {
+        timeout_ms
+    });
+
+    let result = handle
+        .write_bulk(out_endpoint, data, timeout)
+        .map_err(|e| format!("USB write failed: {}", e));
+
+    handle.release_interface(interface_number).ok();
+
+    result.map(|_| ())
+}